@@ -16,7 +16,7 @@ use jni::{
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc;
 
-use crate::{Message, source};
+use crate::{Event, source};
 
 // Global runtime for async operations
 static RUNTIME: OnceLock<Runtime> = OnceLock::new();
@@ -25,7 +25,7 @@ static STREAMS: OnceLock<std::sync::Mutex<HashMap<u64, StreamHandle>>> = OnceLoc
 
 struct StreamHandle {
     sender: mpsc::UnboundedSender<()>,
-    receiver: std::sync::Mutex<mpsc::UnboundedReceiver<Message>>,
+    receiver: std::sync::Mutex<mpsc::UnboundedReceiver<Event>>,
 }
 
 fn get_runtime() -> &'static Runtime {
@@ -46,29 +46,73 @@ fn from_java_string(env: &mut JNIEnv, s: JString) -> Result<String, jni::errors:
     Ok(env.get_string(&s)?.into())
 }
 
-// Message class methods
+// Event kind codes surfaced to Java via getEventType.
+const EVENT_TYPE_CHAT: jint = 0;
+const EVENT_TYPE_DONATION: jint = 1;
+const EVENT_TYPE_STREAM_STATUS: jint = 2;
+const EVENT_TYPE_SYSTEM: jint = 3;
+
+// Message (Event) class methods
 #[unsafe(no_mangle)]
-pub extern "system" fn Java_dev_aperso_monochat_Message_getSender(
+pub extern "system" fn Java_dev_aperso_monochat_Message_getEventType(
+    _env: JNIEnv,
+    _class: JClass,
+    ptr: jlong,
+) -> jint {
+    let event = unsafe { &*(ptr as *const Event) };
+    match event {
+        Event::Chat { .. } => EVENT_TYPE_CHAT,
+        Event::Donation { .. } => EVENT_TYPE_DONATION,
+        Event::StreamStatus { .. } => EVENT_TYPE_STREAM_STATUS,
+        Event::System { .. } => EVENT_TYPE_SYSTEM,
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_dev_aperso_monochat_Message_getSource(
     mut env: JNIEnv,
     _class: JClass,
     ptr: jlong,
 ) -> jstring {
-    let message = unsafe { &*(ptr as *const Message) };
-    match to_java_string(&mut env, &message.sender) {
+    let event = unsafe { &*(ptr as *const Event) };
+    match to_java_string(&mut env, event.source()) {
         Ok(s) => s,
         Err(_) => std::ptr::null_mut(),
     }
 }
 
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_dev_aperso_monochat_Message_getSender(
+    mut env: JNIEnv,
+    _class: JClass,
+    ptr: jlong,
+) -> jstring {
+    let event = unsafe { &*(ptr as *const Event) };
+    match event {
+        Event::Chat { sender, .. } | Event::Donation { sender, .. } => {
+            match to_java_string(&mut env, sender) {
+                Ok(s) => s,
+                Err(_) => std::ptr::null_mut(),
+            }
+        }
+        Event::StreamStatus { .. } | Event::System { .. } => std::ptr::null_mut(),
+    }
+}
+
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_dev_aperso_monochat_Message_getContent(
     mut env: JNIEnv,
     _class: JClass,
     ptr: jlong,
 ) -> jstring {
-    let message = unsafe { &*(ptr as *const Message) };
-    match &message.content {
-        Some(content) => match to_java_string(&mut env, content) {
+    let event = unsafe { &*(ptr as *const Event) };
+    let text = match event {
+        Event::Chat { content, .. } => Some(content.as_str()),
+        Event::System { text, .. } => Some(text.as_str()),
+        Event::Donation { .. } | Event::StreamStatus { .. } => None,
+    };
+    match text {
+        Some(text) => match to_java_string(&mut env, text) {
             Ok(s) => s,
             Err(_) => std::ptr::null_mut(),
         },
@@ -82,8 +126,40 @@ pub extern "system" fn Java_dev_aperso_monochat_Message_getDonated(
     _class: JClass,
     ptr: jlong,
 ) -> jlong {
-    let message = unsafe { &*(ptr as *const Message) };
-    message.donated.unwrap_or(0) as jlong
+    let event = unsafe { &*(ptr as *const Event) };
+    match event {
+        Event::Donation { amount, .. } => *amount as jlong,
+        _ => 0,
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_dev_aperso_monochat_Message_getOnline(
+    _env: JNIEnv,
+    _class: JClass,
+    ptr: jlong,
+) -> jint {
+    let event = unsafe { &*(ptr as *const Event) };
+    match event {
+        Event::StreamStatus { online, .. } => {
+            if *online {
+                1
+            } else {
+                0
+            }
+        }
+        _ => 0,
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_dev_aperso_monochat_Message_getTimestamp(
+    _env: JNIEnv,
+    _class: JClass,
+    ptr: jlong,
+) -> jlong {
+    let event = unsafe { &*(ptr as *const Event) };
+    event.timestamp()
 }
 
 #[unsafe(no_mangle)]
@@ -92,8 +168,12 @@ pub extern "system" fn Java_dev_aperso_monochat_Message_hasDonation(
     _class: JClass,
     ptr: jlong,
 ) -> jint {
-    let message = unsafe { &*(ptr as *const Message) };
-    if message.donated.is_some() { 1 } else { 0 }
+    let event = unsafe { &*(ptr as *const Event) };
+    if matches!(event, Event::Donation { .. }) {
+        1
+    } else {
+        0
+    }
 }
 
 #[unsafe(no_mangle)]
@@ -104,7 +184,7 @@ pub extern "system" fn Java_dev_aperso_monochat_Message_free(
 ) {
     if ptr != 0 {
         unsafe {
-            let _ = Box::from_raw(ptr as *mut Message);
+            let _ = Box::from_raw(ptr as *mut Event);
         }
     }
 }
@@ -124,6 +204,29 @@ pub extern "system" fn Java_dev_aperso_monochat_MonoChat_init(
     0 // Success
 }
 
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_dev_aperso_monochat_MonoChat_startMetricsServerNative(
+    mut env: JNIEnv,
+    _class: JClass,
+    addr: JString,
+) -> jint {
+    let addr_str = match from_java_string(&mut env, addr) {
+        Ok(s) => s,
+        Err(_) => return -1,
+    };
+    let addr: std::net::SocketAddr = match addr_str.parse() {
+        Ok(addr) => addr,
+        Err(_) => return -1,
+    };
+
+    let runtime = get_runtime();
+    runtime.spawn(async move {
+        let _ = crate::metrics::serve(addr).await;
+    });
+
+    0 // Success
+}
+
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_dev_aperso_monochat_MonoChat_connectChzzkNative(
     mut env: JNIEnv,
@@ -141,13 +244,16 @@ pub extern "system" fn Java_dev_aperso_monochat_MonoChat_connectChzzkNative(
     let (message_tx, message_rx) = mpsc::unbounded_channel();
     let (stop_tx, mut stop_rx) = mpsc::unbounded_channel();
 
-    runtime.spawn(async move {
-        let stream_result = source::chzzk::new(url_str).await;
-        let mut stream = match stream_result {
-            Ok(s) => s,
-            Err(_) => return,
-        };
+    let mut stream = source::reconnecting(
+        move || {
+            let url_str = url_str.clone();
+            async move { source::chzzk::new(url_str).await }
+        },
+        source::BackoffPolicy::default(),
+        "chzzk",
+    );
 
+    runtime.spawn(async move {
         loop {
             tokio::select! {
                 message = stream.next() => {
@@ -178,6 +284,7 @@ pub extern "system" fn Java_dev_aperso_monochat_MonoChat_connectChzzkNative(
             },
         );
     }
+    crate::metrics::stream_opened();
 
     stream_id as jlong
 }
@@ -199,13 +306,16 @@ pub extern "system" fn Java_dev_aperso_monochat_MonoChat_connectSoopNative(
     let (message_tx, message_rx) = mpsc::unbounded_channel();
     let (stop_tx, mut stop_rx) = mpsc::unbounded_channel();
 
-    runtime.spawn(async move {
-        let stream_result = source::soop::new(url_str).await;
-        let mut stream = match stream_result {
-            Ok(s) => s,
-            Err(_) => return,
-        };
+    let mut stream = source::reconnecting(
+        move || {
+            let url_str = url_str.clone();
+            async move { source::soop::new(url_str).await }
+        },
+        source::BackoffPolicy::default(),
+        "soop",
+    );
 
+    runtime.spawn(async move {
         loop {
             tokio::select! {
                 message = stream.next() => {
@@ -236,6 +346,7 @@ pub extern "system" fn Java_dev_aperso_monochat_MonoChat_connectSoopNative(
             },
         );
     }
+    crate::metrics::stream_opened();
 
     stream_id as jlong
 }
@@ -275,6 +386,7 @@ pub extern "system" fn Java_dev_aperso_monochat_MonoChat_closeStreamNative(
     if let Some(handle) = streams_guard.remove(&(stream_id as u64)) {
         let _ = handle.sender.send(());
         // The receiver will be dropped automatically
+        crate::metrics::stream_closed();
         0 // Success
     } else {
         -1 // Invalid stream ID
@@ -1,16 +1,67 @@
 use futures::Stream;
 
+pub mod metrics;
+pub mod projection;
 pub mod source;
+pub mod store;
 
 mod ffi;
 
-#[derive(Debug)]
-pub struct Message {
-    pub sender: String,
-    pub content: Option<String>,
-    pub donated: Option<u64>,
+/// A single event observed on a chat source.
+///
+/// Every variant carries its own `source` (the platform it came from, e.g.
+/// `"chzzk"` or `"soop"`) and `timestamp`: milliseconds since the Unix epoch,
+/// as reported by the source platform (or the local receive time, if the
+/// platform doesn't supply one). The `source` tag is what lets a merged,
+/// multi-platform stream (see [`source::merge`]) be told apart downstream.
+#[derive(Debug, Clone)]
+pub enum Event {
+    Chat {
+        source: &'static str,
+        sender: String,
+        content: String,
+        timestamp: i64,
+    },
+    Donation {
+        source: &'static str,
+        sender: String,
+        amount: u64,
+        timestamp: i64,
+    },
+    /// The broadcaster's live status changed (e.g. SOOP's `SETBJSTAT`).
+    StreamStatus {
+        source: &'static str,
+        online: bool,
+        timestamp: i64,
+    },
+    /// A source-specific notice that doesn't fit another variant.
+    System {
+        source: &'static str,
+        text: String,
+        timestamp: i64,
+    },
 }
 
-pub trait MessageStream: Stream<Item = Message> + Unpin {}
+impl Event {
+    pub fn source(&self) -> &'static str {
+        match self {
+            Event::Chat { source, .. }
+            | Event::Donation { source, .. }
+            | Event::StreamStatus { source, .. }
+            | Event::System { source, .. } => *source,
+        }
+    }
 
-impl<T> MessageStream for T where T: Stream<Item = Message> + Unpin {}
+    pub fn timestamp(&self) -> i64 {
+        match self {
+            Event::Chat { timestamp, .. }
+            | Event::Donation { timestamp, .. }
+            | Event::StreamStatus { timestamp, .. }
+            | Event::System { timestamp, .. } => *timestamp,
+        }
+    }
+}
+
+pub trait MessageStream: Stream<Item = Event> + Unpin {}
+
+impl<T> MessageStream for T where T: Stream<Item = Event> + Unpin {}
@@ -0,0 +1,148 @@
+use std::{convert::Infallible, net::SocketAddr, sync::OnceLock};
+
+use anyhow::Result;
+use http_body_util::Full;
+use hyper::{Request, Response, body::Bytes, server::conn::http1, service::service_fn};
+use hyper_util::rt::TokioIo;
+use prometheus::{Encoder, IntCounterVec, IntGauge, Opts, Registry, TextEncoder};
+use tokio::net::TcpListener;
+
+use crate::Event;
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+static MESSAGES_TOTAL: OnceLock<IntCounterVec> = OnceLock::new();
+static DONATION_AMOUNT_TOTAL: OnceLock<IntCounterVec> = OnceLock::new();
+static DROPPED_PACKETS_TOTAL: OnceLock<IntCounterVec> = OnceLock::new();
+static ACTIVE_STREAMS: OnceLock<IntGauge> = OnceLock::new();
+
+fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(Registry::new)
+}
+
+fn register<M: prometheus::core::Collector + Clone + 'static>(metric: M) -> M {
+    registry()
+        .register(Box::new(metric.clone()))
+        .expect("metric registration should never collide");
+    metric
+}
+
+fn messages_total() -> &'static IntCounterVec {
+    MESSAGES_TOTAL.get_or_init(|| {
+        register(
+            IntCounterVec::new(
+                Opts::new(
+                    "monochat_messages_total",
+                    "Messages received, labeled by source and event kind (chat/donation/stream_status/system)",
+                ),
+                &["source", "kind"],
+            )
+            .expect("valid metric"),
+        )
+    })
+}
+
+fn donation_amount_total() -> &'static IntCounterVec {
+    DONATION_AMOUNT_TOTAL.get_or_init(|| {
+        register(
+            IntCounterVec::new(
+                Opts::new(
+                    "monochat_donation_amount_total",
+                    "Sum of donation amounts received, labeled by source",
+                ),
+                &["source"],
+            )
+            .expect("valid metric"),
+        )
+    })
+}
+
+fn dropped_packets_total() -> &'static IntCounterVec {
+    DROPPED_PACKETS_TOTAL.get_or_init(|| {
+        register(
+            IntCounterVec::new(
+                Opts::new(
+                    "monochat_dropped_packets_total",
+                    "Packets that failed to be received, parsed, or decoded, labeled by source",
+                ),
+                &["source"],
+            )
+            .expect("valid metric"),
+        )
+    })
+}
+
+fn active_streams() -> &'static IntGauge {
+    ACTIVE_STREAMS.get_or_init(|| {
+        register(
+            IntGauge::new(
+                "monochat_active_streams",
+                "Number of live entries in the native stream registry",
+            )
+            .expect("valid metric"),
+        )
+    })
+}
+
+/// Records a successfully decoded event, labeled by its source platform and kind.
+pub fn record_event(event: &Event) {
+    let source = event.source();
+    match event {
+        Event::Chat { .. } => {
+            messages_total().with_label_values(&[source, "chat"]).inc();
+        }
+        Event::Donation { amount, .. } => {
+            messages_total()
+                .with_label_values(&[source, "donation"])
+                .inc();
+            donation_amount_total()
+                .with_label_values(&[source])
+                .inc_by(*amount);
+        }
+        Event::StreamStatus { .. } => {
+            messages_total()
+                .with_label_values(&[source, "stream_status"])
+                .inc();
+        }
+        Event::System { .. } => {
+            messages_total()
+                .with_label_values(&[source, "system"])
+                .inc();
+        }
+    }
+}
+
+/// Records a packet that failed to be received, parsed, or decoded.
+pub fn record_dropped(source: &str) {
+    dropped_packets_total().with_label_values(&[source]).inc();
+}
+
+pub fn stream_opened() {
+    active_streams().inc();
+}
+
+pub fn stream_closed() {
+    active_streams().dec();
+}
+
+async fn serve_metrics(_request: Request<hyper::body::Incoming>) -> Result<Response<Full<Bytes>>, Infallible> {
+    let metric_families = registry().gather();
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&metric_families, &mut buffer)
+        .expect("encoding to a Vec<u8> never fails");
+    Ok(Response::new(Full::new(Bytes::from(buffer))))
+}
+
+/// Serves the `/metrics` endpoint on `addr` until the process shuts down.
+pub async fn serve(addr: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let io = TokioIo::new(stream);
+        tokio::spawn(async move {
+            let _ = http1::Builder::new()
+                .serve_connection(io, service_fn(serve_metrics))
+                .await;
+        });
+    }
+}
@@ -0,0 +1,358 @@
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use anyhow::{Result, anyhow};
+use futures::{Stream, StreamExt};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, ToSocketAddrs, tcp::OwnedWriteHalf},
+    sync::{broadcast, mpsc},
+    task,
+};
+
+use crate::{
+    Event, source,
+    store::{Store, StoreWriter},
+};
+
+const SERVER_NAME: &str = "monochat";
+/// How many stored messages to replay to a client on `JOIN`.
+const HISTORY_REPLAY: usize = 50;
+
+static LABELS: OnceLock<Mutex<HashMap<String, &'static str>>> = OnceLock::new();
+
+/// Interns `name` into a process-lifetime `&'static str`, reusing the same
+/// leaked string across repeated (re)opens of the same channel rather than
+/// leaking a fresh one every time a channel dies and gets rejoined.
+fn intern_label(name: &str) -> &'static str {
+    let mut labels = LABELS.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+    if let Some(label) = labels.get(name) {
+        return label;
+    }
+    let label: &'static str = Box::leak(name.to_string().into_boxed_str());
+    labels.insert(name.to_string(), label);
+    label
+}
+
+/// Shared registry of live chat channels, keyed by IRC channel name (e.g.
+/// `#chzzk-<uuid>`, `#soop-<key>`). Each channel lazily spins up the
+/// underlying source stream on first join and fans it out to every
+/// subsequently joined client via a broadcast channel.
+pub struct Gateway {
+    store: Option<Arc<Store>>,
+    channels: Mutex<HashMap<String, broadcast::Sender<Event>>>,
+}
+
+impl Gateway {
+    pub fn new(store: Option<Arc<Store>>) -> Self {
+        Self {
+            store,
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn subscribe(self: &Arc<Self>, channel: &str) -> Result<broadcast::Receiver<Event>> {
+        if let Some(tx) = self.channels.lock().unwrap().get(channel) {
+            return Ok(tx.subscribe());
+        }
+
+        let source_name = channel.trim_start_matches('#').to_string();
+        let stream = open_source(&source_name).await?;
+
+        let (tx, rx) = broadcast::channel(1024);
+        self.channels
+            .lock()
+            .unwrap()
+            .insert(channel.to_string(), tx.clone());
+
+        let store = self.store.clone();
+        let label = intern_label(&source_name);
+        let gateway = Arc::clone(self);
+        let channel_name = channel.to_string();
+        tokio::spawn(async move {
+            let mut stream: Pin<Box<dyn Stream<Item = Event> + Send>> = match store {
+                Some(store) => Box::pin(StoreWriter::wrap(store, label, stream)),
+                None => stream,
+            };
+            while let Some(message) = stream.next().await {
+                // No subscribers yet is fine; the message is just dropped.
+                let _ = tx.send(message);
+            }
+            // The source ended for good (reconnecting has already exhausted
+            // its own retries). Drop the stale entry so the next JOIN opens
+            // a fresh stream instead of subscribing to a dead producer.
+            gateway.channels.lock().unwrap().remove(&channel_name);
+        });
+
+        Ok(rx)
+    }
+}
+
+/// Maps an IRC channel name of the form `<platform>-<id>` to a live source
+/// stream, e.g. `chzzk-<uuid>` or `soop-<key>`. Each stream is wrapped in
+/// [`source::reconnecting`] so a single drop on the remote end doesn't
+/// permanently wedge the channel.
+///
+/// `both-<chzzkId>:<soopKey>` follows the same broadcaster on both
+/// platforms at once and [`source::merge`]s them into a single feed, so a
+/// client only has to `JOIN` one channel to see both.
+async fn open_source(name: &str) -> Result<Pin<Box<dyn Stream<Item = Event> + Send>>> {
+    let (platform, id) = name
+        .split_once('-')
+        .ok_or_else(|| anyhow!("channel name must be `<platform>-<id>`, e.g. `chzzk-<uuid>`"))?;
+    match platform {
+        "chzzk" => Ok(chzzk_source(id)),
+        "soop" => Ok(soop_source(id)),
+        "both" => {
+            let (chzzk_id, soop_key) = id.split_once(':').ok_or_else(|| {
+                anyhow!("`both` channel name must be `both-<chzzkId>:<soopKey>`")
+            })?;
+            Ok(Box::pin(source::merge([
+                chzzk_source(chzzk_id),
+                soop_source(soop_key),
+            ])))
+        }
+        other => Err(anyhow!("unknown chat source `{other}`")),
+    }
+}
+
+fn chzzk_source(id: &str) -> Pin<Box<dyn Stream<Item = Event> + Send>> {
+    let id = id.to_string();
+    Box::pin(source::reconnecting(
+        move || {
+            let id = id.clone();
+            async move {
+                let url =
+                    format!("https://api.chzzk.naver.com/polling/v3.1/channels/{id}/live-status");
+                source::chzzk::new(url).await
+            }
+        },
+        source::BackoffPolicy::default(),
+        "chzzk",
+    ))
+}
+
+fn soop_source(key: &str) -> Pin<Box<dyn Stream<Item = Event> + Send>> {
+    let key = key.to_string();
+    Box::pin(source::reconnecting(
+        move || {
+            let key = key.clone();
+            async move {
+                let url = format!("https://aqua.sooplive.co.kr/component.php?szKey={key}");
+                source::soop::new(url).await
+            }
+        },
+        source::BackoffPolicy::default(),
+        "soop",
+    ))
+}
+
+/// Runs the IRC gateway, accepting connections on `addr` until the process
+/// shuts down. Each client is handled on its own task.
+pub async fn serve(addr: impl ToSocketAddrs, gateway: Arc<Gateway>) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    loop {
+        let (socket, _) = listener.accept().await?;
+        let gateway = gateway.clone();
+        tokio::spawn(async move {
+            let _ = handle_client(socket, gateway).await;
+        });
+    }
+}
+
+async fn handle_client(socket: tokio::net::TcpStream, gateway: Arc<Gateway>) -> Result<()> {
+    let (reader, mut writer) = socket.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    let mut nick = String::from("guest");
+    loop {
+        let Some(line) = lines.next_line().await? else {
+            return Ok(());
+        };
+        let line = line.trim();
+        if let Some(value) = line.strip_prefix("NICK ") {
+            nick = value.trim().to_string();
+        } else if line.starts_with("USER ") {
+            break;
+        }
+    }
+
+    send(
+        &mut writer,
+        format!(":{SERVER_NAME} 001 {nick} :Welcome to monochat\r\n"),
+    )
+    .await?;
+
+    let (forward_tx, mut forward_rx) = mpsc::unbounded_channel::<(String, Event)>();
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else { return Ok(()) };
+                let line = line.trim();
+                if let Some(target) = line.strip_prefix("PING ") {
+                    send(&mut writer, format!("PONG {SERVER_NAME} {target}\r\n")).await?;
+                } else if let Some(channel) = line.strip_prefix("JOIN ") {
+                    let channel = channel.trim().to_string();
+                    match gateway.subscribe(&channel).await {
+                        Ok(rx) => {
+                            send(&mut writer, format!(":{nick} JOIN {channel}\r\n")).await?;
+                            replay_history(&gateway, &channel, &mut writer).await?;
+                            spawn_forwarder(channel, rx, forward_tx.clone());
+                        }
+                        Err(error) => {
+                            send(
+                                &mut writer,
+                                format!(":{SERVER_NAME} 403 {nick} {channel} :{error}\r\n"),
+                            )
+                            .await?;
+                        }
+                    }
+                }
+            }
+            Some((channel, message)) = forward_rx.recv() => {
+                forward(&mut writer, &channel, &message).await?;
+            }
+        }
+    }
+}
+
+fn spawn_forwarder(
+    channel: String,
+    mut rx: broadcast::Receiver<Event>,
+    tx: mpsc::UnboundedSender<(String, Event)>,
+) {
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(message) => {
+                    if tx.send((channel.clone(), message)).is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+async fn replay_history(gateway: &Gateway, channel: &str, writer: &mut OwnedWriteHalf) -> Result<()> {
+    let Some(store) = gateway.store.clone() else {
+        return Ok(());
+    };
+    let source_name = channel.trim_start_matches('#').to_string();
+    let history =
+        task::spawn_blocking(move || store.fetch_history(&source_name, None, HISTORY_REPLAY))
+            .await??;
+    for message in history.into_iter().rev() {
+        forward(writer, channel, &message).await?;
+    }
+    Ok(())
+}
+
+async fn forward(writer: &mut OwnedWriteHalf, channel: &str, event: &Event) -> Result<()> {
+    match event {
+        Event::Chat { sender, content, .. } => {
+            let sender = sanitize_nick(sender);
+            let content = sanitize_content(content);
+            send(
+                writer,
+                format!(":{sender}!{sender}@monochat PRIVMSG {channel} :{content}\r\n"),
+            )
+            .await
+        }
+        Event::Donation { sender, amount, .. } => {
+            let sender = sanitize_nick(sender);
+            send(
+                writer,
+                format!(":{SERVER_NAME} NOTICE {channel} :{sender} donated {amount}\r\n"),
+            )
+            .await
+        }
+        Event::StreamStatus { online, .. } => {
+            let status = if *online { "online" } else { "offline" };
+            send(
+                writer,
+                format!(":{SERVER_NAME} NOTICE {channel} :stream is now {status}\r\n"),
+            )
+            .await
+        }
+        Event::System { text, .. } => {
+            let text = sanitize_content(text);
+            send(writer, format!(":{SERVER_NAME} NOTICE {channel} :{text}\r\n")).await
+        }
+    }
+}
+
+/// Sanitizes a value placed in the IRC **prefix** position (`:{sender}!...`),
+/// which a client reads up to the first space — so, unlike a trailing
+/// parameter, even an embedded space (no CRLF needed) lets a remote chat
+/// participant terminate the prefix early and inject their own command,
+/// params, and trailing text into the same line. Restricts the result to a
+/// nick-safe charset, replacing whitespace and the structural delimiters
+/// `!`, `@`, `:` (and any CR/LF) with `_`.
+fn sanitize_nick(value: &str) -> String {
+    let cleaned: String = value
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || matches!(c, '!' | '@' | ':' | '\r' | '\n') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    if cleaned.is_empty() {
+        "_".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Sanitizes a value placed in the IRC **trailing parameter** position
+/// (`... :{content}\r\n`), where only embedded CR/LF can break out into a
+/// new protocol line a client would parse as its own command.
+fn sanitize_content(value: &str) -> String {
+    value.replace("\r\n", " ").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_nick_rejects_prefix_breakout() {
+        assert_eq!(
+            sanitize_nick("nick PRIVMSG #victim :pwned"),
+            "nick_PRIVMSG_#victim__pwned"
+        );
+    }
+
+    #[test]
+    fn sanitize_nick_leaves_ordinary_nicks_alone() {
+        assert_eq!(sanitize_nick("normal_nick123"), "normal_nick123");
+    }
+
+    #[test]
+    fn sanitize_nick_never_returns_empty() {
+        assert_eq!(sanitize_nick(""), "_");
+        assert_eq!(sanitize_nick(" "), "_");
+    }
+
+    #[test]
+    fn sanitize_content_strips_crlf_line_injection() {
+        assert_eq!(
+            sanitize_content("hello\r\nPRIVMSG #victim :pwned"),
+            "hello PRIVMSG #victim :pwned"
+        );
+    }
+}
+
+async fn send(writer: &mut OwnedWriteHalf, line: impl AsRef<str>) -> Result<()> {
+    writer.write_all(line.as_ref().as_bytes()).await?;
+    Ok(())
+}
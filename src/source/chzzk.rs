@@ -156,6 +156,8 @@ struct ChatMessage {
     content: String,
     #[serde(rename = "extras", deserialize_with = "deserialize_donated")]
     donated: Option<u64>,
+    #[serde(rename = "msgTime")]
+    timestamp: i64,
 }
 
 fn deserialize_sender<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
@@ -204,18 +206,35 @@ pub async fn new(url: impl IntoUrl) -> Result<impl MessageStream> {
     let stream = recv
         .filter_map(|message| async {
             let Ok(message) = message else {
+                crate::metrics::record_dropped("chzzk");
                 return Some(stream::iter(Vec::new()));
             };
             let Ok(command) = ChatCommand::recv(&message) else {
+                crate::metrics::record_dropped("chzzk");
                 return Some(stream::iter(Vec::new()));
             };
             Some(stream::iter(command))
         })
         .flatten()
-        .map(|message| crate::Message {
-            sender: message.sender,
-            content: Some(message.content),
-            donated: message.donated,
+        .flat_map(|message| {
+            let mut events = vec![crate::Event::Chat {
+                source: "chzzk",
+                sender: message.sender.clone(),
+                content: message.content,
+                timestamp: message.timestamp,
+            }];
+            if let Some(amount) = message.donated {
+                events.push(crate::Event::Donation {
+                    source: "chzzk",
+                    sender: message.sender,
+                    amount,
+                    timestamp: message.timestamp,
+                });
+            }
+            for event in &events {
+                crate::metrics::record_event(event);
+            }
+            stream::iter(events)
         })
         .take_until(async move {
             loop {
@@ -0,0 +1,190 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures::{Stream, StreamExt, stream};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::Instant,
+};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use crate::{Event, MessageStream};
+
+pub mod chzzk;
+pub mod soop;
+
+/// Text carried by the marker [`Event::System`] that [`reconnecting`] injects
+/// each time the underlying stream ends or the factory fails to produce one,
+/// before it waits to retry, so consumers (and any history backfill) can tell
+/// that a gap may exist around this point.
+pub const RECONNECT_MARKER_TEXT: &str = "reconnected";
+
+/// Exponential backoff policy for [`reconnecting`].
+#[derive(Debug, Clone, Copy)]
+pub struct BackoffPolicy {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: f64,
+    /// Fraction of the delay (0.0–1.0) to add as random jitter, to avoid
+    /// synchronized reconnect storms across many streams.
+    pub jitter: f64,
+    /// Reconnect attempts permitted before giving up entirely, or `None` to
+    /// retry forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_attempts: None,
+        }
+    }
+}
+
+/// Wraps a stream `factory` so it's transparently re-run whenever the stream
+/// it produces ends or the factory itself fails, following `policy`. The
+/// delay resets once a connection has stayed up long enough to count as
+/// healthy, so a single flaky reconnect doesn't poison later ones, and a
+/// connection that fails instantly doesn't spin in a tight loop.
+///
+/// `source` tags the synthetic reconnect-marker event (see
+/// [`RECONNECT_MARKER_TEXT`]); it should match the `source` the `factory`'s
+/// own events carry.
+///
+/// Dropping the returned stream stops retrying and tears down the
+/// in-flight connection (or pending reconnect sleep) immediately, rather
+/// than leaving it running until it happens to produce or fail to send
+/// another message — the spawned task selects on a cancellation signal
+/// tied to the stream's lifetime at every await point.
+pub fn reconnecting<F, Fut, S>(
+    factory: F,
+    policy: BackoffPolicy,
+    source: &'static str,
+) -> ReconnectingStream
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<S>> + Send + 'static,
+    S: MessageStream + Send + 'static,
+{
+    let (tx, rx) = mpsc::unbounded_channel();
+    let (cancel_tx, mut cancel_rx) = oneshot::channel();
+
+    tokio::spawn(async move {
+        let mut delay = policy.initial_delay;
+        let mut attempts = 0u32;
+
+        loop {
+            if let Some(max_attempts) = policy.max_attempts {
+                if attempts >= max_attempts {
+                    break;
+                }
+            }
+            attempts += 1;
+
+            let connected_at = Instant::now();
+            let mut saw_any = false;
+            tokio::select! {
+                _ = &mut cancel_rx => return,
+                connected = factory() => {
+                    if let Ok(mut stream) = connected {
+                        loop {
+                            tokio::select! {
+                                _ = &mut cancel_rx => return,
+                                message = stream.next() => {
+                                    let Some(message) = message else { break };
+                                    saw_any = true;
+                                    if tx.send(message).is_err() {
+                                        return;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if saw_any && connected_at.elapsed() > policy.initial_delay {
+                attempts = 0;
+                delay = policy.initial_delay;
+            }
+
+            if tx.send(reconnect_marker(source)).is_err() {
+                return;
+            }
+
+            tokio::select! {
+                _ = &mut cancel_rx => return,
+                _ = tokio::time::sleep(jittered(delay, policy.jitter)) => {}
+            }
+            delay = Duration::from_secs_f64(
+                (delay.as_secs_f64() * policy.multiplier).min(policy.max_delay.as_secs_f64()),
+            );
+        }
+    });
+
+    ReconnectingStream {
+        inner: UnboundedReceiverStream::new(rx),
+        _cancel: cancel_tx,
+    }
+}
+
+/// The stream returned by [`reconnecting`]. Carries the cancellation signal
+/// for its backing task: dropping this value (e.g. because the caller is
+/// done with the channel and drops it) tells that task to stop eagerly
+/// instead of discovering it the next time it tries to deliver a message.
+pub struct ReconnectingStream {
+    inner: UnboundedReceiverStream<Event>,
+    _cancel: oneshot::Sender<()>,
+}
+
+impl Stream for ReconnectingStream {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
+}
+
+impl Unpin for ReconnectingStream {}
+
+fn reconnect_marker(source: &'static str) -> Event {
+    Event::System {
+        source,
+        text: RECONNECT_MARKER_TEXT.to_string(),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0),
+    }
+}
+
+fn jittered(delay: Duration, jitter: f64) -> Duration {
+    if jitter <= 0.0 {
+        return delay;
+    }
+    let factor = 1.0 + rand::random::<f64>() * jitter;
+    Duration::from_secs_f64(delay.as_secs_f64() * factor)
+}
+
+/// Interleaves several heterogeneous [`MessageStream`]s into one, e.g. to
+/// follow a SOOP and a Chzzk stream for the same broadcaster as a single
+/// feed. Each event still carries its own originating `source`, so
+/// downstream consumers can tell streams apart. When one sub-stream ends,
+/// the rest keep flowing; the merged stream only ends once all of them have.
+///
+/// Composes cleanly with [`reconnecting`]: wrap each sub-stream in its own
+/// `reconnecting(..)` call first, so a drop on one platform doesn't affect
+/// the others.
+pub fn merge(
+    streams: impl IntoIterator<Item = Pin<Box<dyn Stream<Item = Event> + Send>>>,
+) -> impl MessageStream {
+    stream::select_all(streams)
+}
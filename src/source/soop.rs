@@ -3,7 +3,7 @@ use std::{
         Arc,
         atomic::{AtomicBool, Ordering},
     },
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::{Result, anyhow};
@@ -13,7 +13,7 @@ use serde::Deserialize;
 use tokio::{spawn, time::sleep};
 use tokio_tungstenite::tungstenite::{self, ClientRequestBuilder};
 
-use crate::{Message, MessageStream};
+use crate::{Event, MessageStream};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -67,7 +67,14 @@ pub fn parse_packet(packet: &str) -> Result<(u32, &str)> {
     Ok((packet_type, &packet[HEADER_LEN..]))
 }
 
-pub fn handle_chatmesg(body: &str) -> Result<Message> {
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+pub fn handle_chatmesg(body: &str) -> Result<Event> {
     let mut body = body.split("\x0C");
     let message = body
         .nth(1)
@@ -75,14 +82,15 @@ pub fn handle_chatmesg(body: &str) -> Result<Message> {
     let name = body
         .nth(4)
         .ok_or_else(|| anyhow!("invalid chatmesg packet"))?;
-    Ok(Message {
+    Ok(Event::Chat {
+        source: "soop",
         sender: name.to_string(),
-        content: Some(message.to_string()),
-        donated: None,
+        content: message.to_string(),
+        timestamp: now_millis(),
     })
 }
 
-pub fn handle_sendballoon(body: &str) -> Result<Message> {
+pub fn handle_sendballoon(body: &str) -> Result<Event> {
     let mut body = body.split("\x0C");
     let user = body
         .nth(2)
@@ -90,33 +98,43 @@ pub fn handle_sendballoon(body: &str) -> Result<Message> {
     let amount = body
         .nth(1)
         .ok_or_else(|| anyhow!("invalid sendballoon packet"))?;
-    Ok(Message {
+    Ok(Event::Donation {
+        source: "soop",
         sender: user.to_string(),
-        content: None,
-        donated: Some(
-            amount
-                .parse::<u64>()
-                .map_err(|_| anyhow!("failed to parse amount"))?,
-        ),
+        amount: amount
+            .parse::<u64>()
+            .map_err(|_| anyhow!("failed to parse amount"))?,
+        timestamp: now_millis(),
     })
 }
 
-fn handle_setbjstat(is_alive: &Arc<AtomicBool>) -> Result<Message> {
+fn handle_setbjstat(is_alive: &Arc<AtomicBool>) -> Result<Event> {
     is_alive.store(false, Ordering::Relaxed);
-    Err(anyhow!("continue"))
+    Ok(Event::StreamStatus {
+        source: "soop",
+        online: false,
+        timestamp: now_millis(),
+    })
 }
 
-fn handle_message(message: tungstenite::Message, is_alive: &Arc<AtomicBool>) -> Result<Message> {
-    let text = message
-        .into_text()
-        .map_err(|_| anyhow!("failed to convert message to text"))?;
-    let (packet_type, body) = parse_packet(&text)?;
-    match packet_type {
-        0x05 => handle_chatmesg(body),
+fn handle_message(message: tungstenite::Message, is_alive: &Arc<AtomicBool>) -> Result<Event> {
+    let text = message.into_text().map_err(|_| {
+        crate::metrics::record_dropped("soop");
+        anyhow!("failed to convert message to text")
+    })?;
+    let (packet_type, body) = parse_packet(&text).inspect_err(|_| {
+        crate::metrics::record_dropped("soop");
+    })?;
+    let event = match packet_type {
+        0x05 => handle_chatmesg(body).inspect_err(|_| crate::metrics::record_dropped("soop")),
         0x07 => handle_setbjstat(is_alive),
-        0x12 => handle_sendballoon(body),
+        0x12 => handle_sendballoon(body).inspect_err(|_| crate::metrics::record_dropped("soop")),
         _ => Err(anyhow!("continue")),
+    };
+    if let Ok(event) = &event {
+        crate::metrics::record_event(event);
     }
+    event
 }
 
 /// https://aqua.sooplive.co.kr/component.php?szKey=<key>
@@ -170,7 +188,10 @@ pub async fn new(aqua_url: impl IntoUrl) -> Result<impl MessageStream> {
                         Ok(message) => Some(message),
                         Err(_) => None,
                     },
-                    Err(_) => None,
+                    Err(_) => {
+                        crate::metrics::record_dropped("soop");
+                        None
+                    }
                 }
             }
         })
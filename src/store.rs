@@ -0,0 +1,221 @@
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use futures::{Stream, StreamExt};
+use rusqlite::{Connection, params};
+use tokio::{sync::mpsc, task, time};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use crate::{Event, MessageStream};
+
+/// Maps a stored `platform` column value back to the `&'static str` that
+/// [`Event::source`] expects, without leaking memory per row. Anything we
+/// don't recognize (e.g. a future platform added after this row was written)
+/// falls back to `"unknown"` rather than failing the whole query.
+fn intern_platform(platform: &str) -> &'static str {
+    match platform {
+        "chzzk" => "chzzk",
+        "soop" => "soop",
+        _ => "unknown",
+    }
+}
+
+/// How many messages to buffer before an out-of-band flush kicks in.
+const BATCH_SIZE: usize = 32;
+/// Upper bound on how long a message can sit unflushed.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A SQLite-backed store for aggregated chat history.
+pub struct Store {
+    conn: Mutex<Connection>,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let conn = Connection::open(path).context("failed to open chat store")?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS events (
+                id        INTEGER PRIMARY KEY,
+                source    TEXT NOT NULL,
+                platform  TEXT NOT NULL,
+                kind      TEXT NOT NULL,
+                sender    TEXT,
+                content   TEXT,
+                donated   INTEGER,
+                online    INTEGER,
+                timestamp INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS events_source_timestamp
+                ON events (source, timestamp);",
+        )
+        .context("failed to set up chat store schema")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn insert_batch(&self, source: &str, events: &[Event]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO events (source, platform, kind, sender, content, donated, online, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for event in events {
+                let (kind, sender, content, donated, online) = match event {
+                    Event::Chat { sender, content, .. } => {
+                        ("chat", Some(sender.as_str()), Some(content.as_str()), None, None)
+                    }
+                    Event::Donation { sender, amount, .. } => {
+                        ("donation", Some(sender.as_str()), None, Some(*amount as i64), None)
+                    }
+                    Event::StreamStatus { online, .. } => {
+                        ("stream_status", None, None, None, Some(*online as i64))
+                    }
+                    Event::System { text, .. } => ("system", None, Some(text.as_str()), None, None),
+                };
+                stmt.execute(params![
+                    source,
+                    event.source(),
+                    kind,
+                    sender,
+                    content,
+                    donated,
+                    online,
+                    event.timestamp(),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Returns up to `limit` events from `source`, most recent first, that
+    /// occurred before the `before` cursor (or all of them, if `None`).
+    pub fn fetch_history(
+        &self,
+        source: &str,
+        before: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT platform, kind, sender, content, donated, online, timestamp FROM events
+             WHERE source = ?1 AND timestamp < ?2
+             ORDER BY timestamp DESC
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(
+            params![source, before.unwrap_or(i64::MAX), limit as i64],
+            |row| {
+                let source = intern_platform(&row.get::<_, String>(0)?);
+                let kind: String = row.get(1)?;
+                let timestamp: i64 = row.get(6)?;
+                Ok(match kind.as_str() {
+                    "donation" => Event::Donation {
+                        source,
+                        sender: row.get(2)?,
+                        amount: row.get::<_, i64>(4)? as u64,
+                        timestamp,
+                    },
+                    "stream_status" => Event::StreamStatus {
+                        source,
+                        online: row.get::<_, i64>(5)? != 0,
+                        timestamp,
+                    },
+                    "system" => Event::System {
+                        source,
+                        text: row.get(3)?,
+                        timestamp,
+                    },
+                    _ => Event::Chat {
+                        source,
+                        sender: row.get(2)?,
+                        content: row.get(3)?,
+                        timestamp,
+                    },
+                })
+            },
+        )?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("failed to read chat history")
+    }
+}
+
+/// Wraps a [`MessageStream`] so that every message is persisted to a [`Store`]
+/// as it arrives, while still being forwarded to the caller unchanged.
+///
+/// Inserts are batched (by count and by time) and run on a blocking task so
+/// the SQLite write path never blocks the async runtime. The wrapped stream
+/// is not read while a batch is being written.
+pub struct StoreWriter {
+    inner: UnboundedReceiverStream<Event>,
+}
+
+impl StoreWriter {
+    pub fn wrap(
+        store: Arc<Store>,
+        source: &'static str,
+        mut stream: impl MessageStream + Send + 'static,
+    ) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let mut batch = Vec::new();
+            let mut flush = time::interval(FLUSH_INTERVAL);
+            flush.tick().await; // first tick fires immediately
+
+            loop {
+                tokio::select! {
+                    message = stream.next() => {
+                        let Some(message) = message else { break };
+                        batch.push(message.clone());
+                        if tx.send(message).is_err() {
+                            break;
+                        }
+                        if batch.len() >= BATCH_SIZE {
+                            flush_batch(&store, source, &mut batch).await;
+                        }
+                    }
+                    _ = flush.tick() => {
+                        flush_batch(&store, source, &mut batch).await;
+                    }
+                }
+            }
+
+            flush_batch(&store, source, &mut batch).await;
+        });
+
+        Self {
+            inner: UnboundedReceiverStream::new(rx),
+        }
+    }
+}
+
+async fn flush_batch(store: &Arc<Store>, source: &'static str, batch: &mut Vec<Event>) {
+    if batch.is_empty() {
+        return;
+    }
+    let to_flush = std::mem::take(batch);
+    let store = store.clone();
+    let _ = task::spawn_blocking(move || store.insert_batch(source, &to_flush)).await;
+}
+
+impl Stream for StoreWriter {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
+}
+
+impl Unpin for StoreWriter {}